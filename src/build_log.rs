@@ -1,8 +1,10 @@
 use std::{
+    collections::{HashMap, HashSet},
     fmt::{self, Display},
     path::{Path, PathBuf},
 };
 
+use itertools::Itertools;
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -56,7 +58,7 @@ pub struct BuildEdgeInput {
     pub in_type: InputEdgeType,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum InputEdgeType {
     #[serde(rename = "explicit")]
     Explicit,
@@ -73,7 +75,7 @@ pub struct BuildEdgeOutput {
     pub out_type: OutputEdgeType,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum OutputEdgeType {
     #[serde(rename = "explicit")]
     Explicit,
@@ -89,76 +91,401 @@ pub struct BuildEdgeFinished {
     pub output: String,
 }
 
+/// Identifies an edge within a [`BuildState`], newtyped over the raw index
+/// ninja assigns so it can't be confused with a `node_id` or a display
+/// index into the rendered list, and can be used directly as a dense
+/// [`BuildState::entries`] index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(pub usize);
+
+impl Display for EdgeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An input of a [`BuildLogEntry`], retaining the `node_id` so it can be
+/// looked up in the dependency graph, alongside its path for display.
+#[derive(Debug)]
+pub struct LogEdgeInput {
+    pub node_id: i64,
+    pub path: PathBuf,
+    pub in_type: InputEdgeType,
+}
+
+/// An output of a [`BuildLogEntry`], retaining the `node_id` so other edges
+/// that consume it can be found in the dependency graph.
+#[derive(Debug)]
+pub struct LogEdgeOutput {
+    pub node_id: i64,
+    pub path: PathBuf,
+    pub out_type: OutputEdgeType,
+}
+
 //Maybe this should be an enum with a running and finished variant, to avoid multiple Options
-#[derive(Debug, Deserialize)]
+#[derive(Debug)]
 pub struct BuildLogEntry {
-    pub edge_id: usize,
+    pub edge_id: EdgeId,
     pub success: Option<bool>,
     pub command: String,
     pub compiler: String,
-    pub inputs: Vec<PathBuf>,
-    pub outputs: Vec<PathBuf>,
+    pub inputs: Vec<LogEdgeInput>,
+    pub outputs: Vec<LogEdgeOutput>,
     pub output: Option<String>,
+    /// Lowercased `output`, computed once when the edge finishes rather
+    /// than on every filter match, since `output` can be megabytes long.
+    pub output_lower: Option<String>,
     pub start_time_millis: i64,
     pub end_time_millis: Option<i64>,
 }
 
-//TODO Should use an ordered hash map, vec is very inefficient
+/// Holds every edge seen so far, indexed directly by [`EdgeId`] for O(1)
+/// insert/lookup instead of the linear scans a plain `Vec<BuildLogEntry>`
+/// would need, while `order` keeps track of the order edges started in so
+/// the rendered list doesn't have to care about the dense layout.
+///
+/// `critical_path_cache` and the `completed_edges`/`sum_duration_millis`/
+/// `min_start_millis`/`max_timestamp_millis` counters below are all kept up
+/// to date in [`Self::update`] rather than computed when read, since their
+/// readers are called on every UI redraw. The counters are updated
+/// incrementally; `critical_path_cache` is recomputed over every entry each
+/// time an edge finishes.
 pub struct BuildState {
-    pub log_entries: Vec<BuildLogEntry>,
+    entries: Vec<Option<BuildLogEntry>>,
+    order: Vec<EdgeId>,
     pub total_edges: usize,
     pub build_status: BuildStatus,
+    /// node_id of an output -> the edge_id of the edge that produces it.
+    node_producers: HashMap<i64, EdgeId>,
+    /// node_id of an input -> the edge_ids of the edges that consume it.
+    node_consumers: HashMap<i64, Vec<EdgeId>>,
+    /// Cached result of [`Self::critical_path`].
+    critical_path_cache: Vec<EdgeId>,
+    /// Count of edges with an `end_time_millis`.
+    completed_edges: usize,
+    /// Sum of `end_time_millis - start_time_millis` over completed edges.
+    sum_duration_millis: i64,
+    /// Earliest `start_time_millis` seen so far.
+    min_start_millis: Option<i64>,
+    /// Latest timestamp seen so far, across both start and end times.
+    max_timestamp_millis: i64,
 }
 
 impl BuildState {
     pub fn new() -> Self {
         Self {
-            log_entries: Vec::new(),
+            entries: Vec::new(),
+            order: Vec::new(),
             total_edges: 0,
             build_status: BuildStatus::NotStarted,
+            node_producers: HashMap::new(),
+            node_consumers: HashMap::new(),
+            critical_path_cache: Vec::new(),
+            completed_edges: 0,
+            sum_duration_millis: 0,
+            min_start_millis: None,
+            max_timestamp_millis: 0,
         }
     }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    pub fn get(&self, edge_id: EdgeId) -> Option<&BuildLogEntry> {
+        self.entries.get(edge_id.0).and_then(Option::as_ref)
+    }
+
+    /// The entry at `display_index` in the order edges started, i.e. the
+    /// index the rendered log list uses.
+    pub fn entry_at(&self, display_index: usize) -> Option<&BuildLogEntry> {
+        self.order.get(display_index).and_then(|&id| self.get(id))
+    }
+
+    /// The display index of `edge_id`, if it has been started.
+    pub fn index_of(&self, edge_id: EdgeId) -> Option<usize> {
+        self.order.iter().position(|&id| id == edge_id)
+    }
+
+    pub fn entries_in_order(&self) -> impl Iterator<Item = &BuildLogEntry> {
+        self.order.iter().filter_map(move |&id| self.get(id))
+    }
+
     pub fn update(&mut self, message: StructLogMessage) {
         match message {
             StructLogMessage::BuildEdgeStarted(started) => {
-                assert!(self
-                    .log_entries
-                    .iter()
-                    .find(|e| e.edge_id == started.edge_id)
-                    .is_none());
+                let edge_id = EdgeId(started.edge_id);
+                if edge_id.0 >= self.entries.len() {
+                    self.entries.resize_with(edge_id.0 + 1, || None);
+                }
+                assert!(
+                    self.entries[edge_id.0].is_none(),
+                    "duplicate build_edge_started for {edge_id}"
+                );
                 let command_short = guess_compiler(&started.command).unwrap_or("???".to_owned());
-                self.log_entries.push(BuildLogEntry {
-                    edge_id: started.edge_id,
+
+                for input in &started.inputs {
+                    self.node_consumers
+                        .entry(input.node_id)
+                        .or_default()
+                        .push(edge_id);
+                }
+                for output in &started.outputs {
+                    self.node_producers.insert(output.node_id, edge_id);
+                }
+
+                self.entries[edge_id.0] = Some(BuildLogEntry {
+                    edge_id,
                     success: None,
                     command: started.command,
                     compiler: command_short,
                     inputs: started
                         .inputs
-                        .iter()
-                        .filter(|e| matches!(e.in_type, InputEdgeType::Explicit))
-                        .map(|o| o.path.to_owned())
+                        .into_iter()
+                        .map(|i| LogEdgeInput {
+                            node_id: i.node_id,
+                            path: i.path,
+                            in_type: i.in_type,
+                        })
+                        .collect(),
+                    outputs: started
+                        .outputs
+                        .into_iter()
+                        .map(|o| LogEdgeOutput {
+                            node_id: o.node_id,
+                            path: o.path,
+                            out_type: o.out_type,
+                        })
                         .collect(),
-                    outputs: started.outputs.iter().map(|o| o.path.to_owned()).collect(),
                     output: None,
+                    output_lower: None,
                     start_time_millis: started.start_time_millis,
                     end_time_millis: None,
-                })
+                });
+                self.order.push(edge_id);
+
+                self.min_start_millis = Some(
+                    self.min_start_millis
+                        .map_or(started.start_time_millis, |min| min.min(started.start_time_millis)),
+                );
+                self.max_timestamp_millis = self.max_timestamp_millis.max(started.start_time_millis);
             }
             StructLogMessage::BuildEdgeFinished(finished) => {
-                let entry: &mut BuildLogEntry = self
-                    .log_entries
-                    .iter_mut()
-                    .find(|e| e.edge_id == finished.edge_id)
+                let edge_id = EdgeId(finished.edge_id);
+                let entry = self
+                    .entries
+                    .get_mut(edge_id.0)
+                    .and_then(Option::as_mut)
                     .expect("There should be a started entry for every finished entry");
 
+                // Guard against double-counting if a finished event is ever
+                // replayed for an edge that's already finished.
+                let already_finished = entry.end_time_millis.is_some();
+
                 entry.success = Some(finished.success);
+                entry.output_lower = Some(finished.output.to_lowercase());
                 entry.output = Some(finished.output);
                 entry.end_time_millis = Some(finished.end_time_millis);
+
+                if !already_finished {
+                    self.completed_edges += 1;
+                    self.sum_duration_millis += Self::duration_millis(entry);
+                }
+                self.max_timestamp_millis = self.max_timestamp_millis.max(finished.end_time_millis);
+
+                self.critical_path_cache = self.compute_critical_path();
             }
             StructLogMessage::TotalEdges { total } => self.total_edges = total,
             StructLogMessage::BuildStatus { status } => self.build_status = status,
         }
     }
+
+    /// Edge ids of the edges that directly produce one of `edge_id`'s inputs.
+    pub fn predecessors(&self, edge_id: EdgeId) -> Vec<EdgeId> {
+        let Some(entry) = self.get(edge_id) else {
+            return Vec::new();
+        };
+        entry
+            .inputs
+            .iter()
+            .filter_map(|i| self.node_producers.get(&i.node_id).copied())
+            .unique()
+            .collect()
+    }
+
+    /// Edge ids of the edges that directly consume one of `edge_id`'s outputs.
+    pub fn successors(&self, edge_id: EdgeId) -> Vec<EdgeId> {
+        let Some(entry) = self.get(edge_id) else {
+            return Vec::new();
+        };
+        entry
+            .outputs
+            .iter()
+            .flat_map(|o| self.node_consumers.get(&o.node_id).cloned().unwrap_or_default())
+            .unique()
+            .collect()
+    }
+
+    fn duration_millis(entry: &BuildLogEntry) -> i64 {
+        match entry.end_time_millis {
+            Some(end) => (end - entry.start_time_millis).max(0),
+            // Still running: we don't have a wall clock to compare against
+            // here, so treat it as having contributed no time yet.
+            None => 0,
+        }
+    }
+
+    /// Computes `finish_cost[e] = duration[e] + max(finish_cost[p] for p in predecessors(e))`
+    /// for `edge_id`, memoizing into `finish_cost` and recording, for each
+    /// edge, which predecessor supplied the maximum in `critical_pred`.
+    /// Edges already on the current DFS stack are skipped so a cycle (which
+    /// shouldn't occur in a real ninja graph) can't cause infinite recursion.
+    fn finish_cost(
+        &self,
+        edge_id: EdgeId,
+        finish_cost: &mut HashMap<EdgeId, i64>,
+        critical_pred: &mut HashMap<EdgeId, EdgeId>,
+        visiting: &mut HashSet<EdgeId>,
+    ) -> i64 {
+        if let Some(&cost) = finish_cost.get(&edge_id) {
+            return cost;
+        }
+        if !visiting.insert(edge_id) {
+            return 0;
+        }
+
+        let Some(entry) = self.get(edge_id) else {
+            visiting.remove(&edge_id);
+            return 0;
+        };
+
+        let mut max_pred_cost = 0;
+        let mut max_pred = None;
+        for pred in self.predecessors(edge_id) {
+            let cost = self.finish_cost(pred, finish_cost, critical_pred, visiting);
+            if cost > max_pred_cost {
+                max_pred_cost = cost;
+                max_pred = Some(pred);
+            }
+        }
+
+        let cost = Self::duration_millis(entry) + max_pred_cost;
+        finish_cost.insert(edge_id, cost);
+        if let Some(pred) = max_pred {
+            critical_pred.insert(edge_id, pred);
+        }
+        visiting.remove(&edge_id);
+        cost
+    }
+
+    /// The chain of edges, in execution order, that determines the length of
+    /// the build: the longest path through the dependency DAG weighted by
+    /// edge duration. Returns the edge ids from the very first edge on the
+    /// chain to the one that finishes last.
+    pub fn critical_path(&self) -> &[EdgeId] {
+        &self.critical_path_cache
+    }
+
+    fn compute_critical_path(&self) -> Vec<EdgeId> {
+        let mut finish_cost = HashMap::new();
+        let mut critical_pred = HashMap::new();
+        let mut visiting = HashSet::new();
+
+        for entry in self.entries_in_order() {
+            self.finish_cost(entry.edge_id, &mut finish_cost, &mut critical_pred, &mut visiting);
+        }
+
+        let Some((&last, _)) = finish_cost.iter().max_by_key(|(_, &cost)| cost) else {
+            return Vec::new();
+        };
+
+        let mut chain = vec![last];
+        let mut current = last;
+        while let Some(&pred) = critical_pred.get(&current) {
+            chain.push(pred);
+            current = pred;
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Total duration in milliseconds of the critical path returned by
+    /// [`BuildState::critical_path`].
+    pub fn critical_path_millis(&self, path: &[EdgeId]) -> i64 {
+        path.iter()
+            .filter_map(|&id| self.get(id))
+            .map(Self::duration_millis)
+            .sum()
+    }
+
+    /// Total wall-clock time elapsed between the first edge starting and the
+    /// last known timestamp (the latest start or finish time seen).
+    pub fn wall_clock_millis(&self) -> i64 {
+        match self.min_start_millis {
+            Some(start) => (self.max_timestamp_millis - start).max(0),
+            None => 0,
+        }
+    }
+
+    /// A snapshot of how far along the build is and how fast it's going.
+    /// The average edge duration behind the ETA is taken over every edge
+    /// completed so far, not over a window of recent edges.
+    pub fn progress(&self) -> BuildProgress {
+        let completed = self.completed_edges;
+        let total = self.total_edges.max(self.len());
+        let elapsed_millis = self.wall_clock_millis();
+
+        let avg_duration_millis = if completed > 0 {
+            self.sum_duration_millis as f64 / completed as f64
+        } else {
+            0.0
+        };
+        // Effective parallelism: how many edges were running, on average, at
+        // any given moment during the elapsed time.
+        let parallelism = if elapsed_millis > 0 {
+            self.sum_duration_millis as f64 / elapsed_millis as f64
+        } else {
+            0.0
+        };
+        let edges_per_sec = if elapsed_millis > 0 {
+            completed as f64 / (elapsed_millis as f64 / 1000.0)
+        } else {
+            0.0
+        };
+        let percent = if total > 0 {
+            completed as f64 / total as f64 * 100.0
+        } else {
+            0.0
+        };
+        let eta_millis = if parallelism > 0.0 && total > completed {
+            Some(((total - completed) as f64 * avg_duration_millis / parallelism) as i64)
+        } else {
+            None
+        };
+
+        BuildProgress {
+            completed,
+            total,
+            elapsed_millis,
+            edges_per_sec,
+            percent,
+            eta_millis,
+        }
+    }
+}
+
+/// Estimated progress and build rate, see [`BuildState::progress`].
+pub struct BuildProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub elapsed_millis: i64,
+    pub edges_per_sec: f64,
+    pub percent: f64,
+    pub eta_millis: Option<i64>,
 }
 
 fn guess_compiler(command: &str) -> Option<String> {
@@ -168,3 +495,80 @@ fn guess_compiler(command: &str) -> Option<String> {
         .and_then(|f| f.to_str())
         .map(|s| s.to_owned())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn started(
+        edge_id: usize,
+        start_time_millis: i64,
+        inputs: Vec<i64>,
+        outputs: Vec<i64>,
+    ) -> StructLogMessage {
+        StructLogMessage::BuildEdgeStarted(BuildEdgeStarted {
+            edge_id,
+            command: "cc -c in -o out".to_owned(),
+            start_time_millis,
+            inputs: inputs
+                .into_iter()
+                .map(|node_id| BuildEdgeInput {
+                    node_id,
+                    path: PathBuf::from("in"),
+                    in_type: InputEdgeType::Explicit,
+                })
+                .collect(),
+            outputs: outputs
+                .into_iter()
+                .map(|node_id| BuildEdgeOutput {
+                    node_id,
+                    path: PathBuf::from("out"),
+                    out_type: OutputEdgeType::Explicit,
+                })
+                .collect(),
+        })
+    }
+
+    fn finished(edge_id: usize, end_time_millis: i64) -> StructLogMessage {
+        StructLogMessage::BuildEdgeFinished(BuildEdgeFinished {
+            edge_id,
+            end_time_millis,
+            success: true,
+            output: String::new(),
+        })
+    }
+
+    #[test]
+    fn critical_path_follows_the_slower_branch_of_a_diamond() {
+        let mut state = BuildState::new();
+        // 0 -> {1, 2} -> 3, with 1 the slower of the two middle branches.
+        state.update(started(0, 0, vec![], vec![1]));
+        state.update(finished(0, 100));
+        state.update(started(1, 100, vec![1], vec![2]));
+        state.update(started(2, 100, vec![1], vec![3]));
+        state.update(finished(2, 150));
+        state.update(finished(1, 300));
+        state.update(started(3, 300, vec![2, 3], vec![]));
+        state.update(finished(3, 400));
+
+        let path = state.critical_path();
+        assert_eq!(path.to_vec(), vec![EdgeId(0), EdgeId(1), EdgeId(3)]);
+        assert_eq!(state.critical_path_millis(path), 400);
+    }
+
+    #[test]
+    fn critical_path_does_not_hang_on_a_cycle() {
+        let mut state = BuildState::new();
+        // 0 and 1 each depend on the other's output, which shouldn't occur
+        // in a real ninja graph but must not send `finish_cost` into
+        // infinite recursion if it ever does.
+        state.update(started(0, 0, vec![2], vec![1]));
+        state.update(started(1, 0, vec![1], vec![2]));
+        state.update(finished(0, 10));
+        state.update(finished(1, 10));
+
+        let path = state.critical_path();
+        assert_eq!(path.to_vec(), vec![EdgeId(1), EdgeId(0)]);
+        assert_eq!(state.critical_path_millis(path), 20);
+    }
+}
@@ -6,7 +6,12 @@ use std::{error::Error, io};
 use std::process;
 
 mod build_log;
-use build_log::{BuildLogEntry, BuildState, StructLogMessage};
+use build_log::{BuildLogEntry, BuildState, EdgeId, InputEdgeType, StructLogMessage};
+
+mod timeline;
+use timeline::{lanes_needed, Timeline, TimelineView};
+
+mod vt;
 
 use std::sync::mpsc;
 use std::thread;
@@ -109,6 +114,15 @@ fn spawn_reader<R: Read + Send + 'static>(reader: R) -> mpsc::Receiver<StructLog
     rx
 }
 
+fn progress_bar(percent: f64, width: usize) -> String {
+    let filled = ((percent / 100.0 * width as f64).round() as usize).min(width);
+    format!("[{}{}]", "=".repeat(filled), " ".repeat(width - filled))
+}
+
+fn format_duration_millis(millis: i64) -> String {
+    format!("{:.1}s", millis as f64 / 1000.0)
+}
+
 fn entry_color(success: Option<bool>) -> Color {
     match success {
         Some(true) | None => Color::Reset,
@@ -121,13 +135,14 @@ fn log_entry_to_list_item(item: &BuildLogEntry) -> ListItem {
     let inputs: String = item
         .inputs
         .iter()
-        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .filter(|i| matches!(i.in_type, InputEdgeType::Explicit))
+        .map(|i| i.path.file_name().unwrap().to_str().unwrap())
         .intersperse(", ")
         .collect();
     let outputs: String = item
         .outputs
         .iter()
-        .map(|p| p.file_name().unwrap().to_str().unwrap())
+        .map(|o| o.path.file_name().unwrap().to_str().unwrap())
         .intersperse(", ")
         .collect();
     let string: String = format!("{}: {} -> {}", item.compiler, inputs, outputs);
@@ -135,15 +150,72 @@ fn log_entry_to_list_item(item: &BuildLogEntry) -> ListItem {
     ListItem::new(text)
 }
 
+fn dependency_type_style(explicit: bool, implicit: bool) -> Style {
+    if explicit {
+        Style::default()
+    } else if implicit {
+        Style::default().fg(Color::DarkGray)
+    } else {
+        Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
+    }
+}
+
+fn input_line(input: &build_log::LogEdgeInput) -> Line {
+    let (tag, explicit, implicit) = match input.in_type {
+        InputEdgeType::Explicit => ("E", true, false),
+        InputEdgeType::Implicit => ("I", false, true),
+        InputEdgeType::OrderOnly => ("O", false, false),
+    };
+    Line::styled(
+        format!("[{}] {}", tag, input.path.display()),
+        dependency_type_style(explicit, implicit),
+    )
+}
+
+fn edge_summary_line(entry: &BuildLogEntry) -> Line<'static> {
+    Line::styled(
+        format!("#{} {}", entry.edge_id, entry.compiler),
+        Style::default().bg(entry_color(entry.success)),
+    )
+}
+
 enum UIEvent {
     BuildLog(StructLogMessage),
     UserAction(crossterm::event::Event),
 }
 
+#[derive(PartialEq, Eq)]
+enum ViewMode {
+    Log,
+    Timeline,
+}
+
 struct App {
     build_state: BuildState,
     list_state: ListState,
     log_receiver: mpsc::Receiver<StructLogMessage>,
+    /// Cycles through the predecessors/successors of `dependency_anchor` each
+    /// time the user jumps, so repeated presses walk the whole list.
+    dependency_cursor: usize,
+    /// The entry a `p`/`s` streak is walking the predecessors/successors of.
+    /// Stays fixed across an uninterrupted streak of jumps (even though each
+    /// jump moves the selection to a different entry) and is only replaced
+    /// once the selection turns out to be somewhere other than where the
+    /// last jump landed, i.e. once something other than `p`/`s` moved it.
+    dependency_anchor: Option<EdgeId>,
+    /// Where the last `p`/`s` jump landed, so the next jump can tell whether
+    /// the selection has moved since (see `dependency_anchor`).
+    dependency_last_target: Option<EdgeId>,
+    view_mode: ViewMode,
+    timeline_view: TimelineView,
+    /// Position along `BuildState::critical_path` the next `c` press lands
+    /// on, so repeated presses walk the chain from start to finish.
+    critical_path_cursor: usize,
+    /// Substring query narrowing the rendered list to matching entries; `/`
+    /// starts editing it, Enter/Esc stops.
+    filter_query: String,
+    filter_editing: bool,
+    failed_only: bool,
 }
 
 impl App {
@@ -152,18 +224,172 @@ impl App {
             build_state: BuildState::new(),
             list_state: ListState::default().with_selected(Some(0)),
             log_receiver,
+            dependency_cursor: 0,
+            dependency_anchor: None,
+            dependency_last_target: None,
+            view_mode: ViewMode::Log,
+            timeline_view: TimelineView::new(),
+            critical_path_cursor: 0,
+            filter_query: String::new(),
+            filter_editing: false,
+            failed_only: false,
         }
     }
 
+    fn matches_filter(&self, entry: &BuildLogEntry) -> bool {
+        if self.failed_only && entry.success != Some(false) {
+            return false;
+        }
+        if self.filter_query.is_empty() {
+            return true;
+        }
+        let query = self.filter_query.to_lowercase();
+        entry.compiler.to_lowercase().contains(&query)
+            || entry
+                .inputs
+                .iter()
+                .any(|i| i.path.to_string_lossy().to_lowercase().contains(&query))
+            || entry
+                .outputs
+                .iter()
+                .any(|o| o.path.to_string_lossy().to_lowercase().contains(&query))
+            || entry
+                .output_lower
+                .as_deref()
+                .is_some_and(|o| o.contains(&query))
+    }
+
+    /// Display indices (into `BuildState`'s insertion order) of the entries
+    /// currently matching the filter query and failed-only toggle.
+    fn filtered_indices(&self) -> Vec<usize> {
+        self.build_state
+            .entries_in_order()
+            .enumerate()
+            .filter(|(_, e)| self.matches_filter(e))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Keeps the list selection within the current filtered view after the
+    /// filter changes and may have shrunk it.
+    fn clamp_selection(&mut self) {
+        let filtered_len = self.filtered_indices().len();
+        if filtered_len == 0 {
+            self.list_state.select(None);
+        } else {
+            let selected = self.list_state.selected().unwrap_or(0).min(filtered_len - 1);
+            self.list_state.select(Some(selected));
+        }
+    }
+
+    fn toggle_view_mode(&mut self) {
+        self.view_mode = match self.view_mode {
+            ViewMode::Log => ViewMode::Timeline,
+            ViewMode::Timeline => ViewMode::Log,
+        };
+    }
+
     fn select_log(&mut self, offset: isize) {
-        if self.build_state.log_entries.is_empty() {
+        let filtered = self.filtered_indices();
+        if filtered.is_empty() {
             self.list_state.select(None);
         } else {
             let selected = self.list_state.selected().unwrap_or(0);
-            let new = usize::saturating_add_signed(selected, offset)
-                .min(self.build_state.log_entries.len() - 1);
+            let new = usize::saturating_add_signed(selected, offset).min(filtered.len() - 1);
             self.list_state.select(Some(new));
         }
+        self.dependency_cursor = 0;
+        self.dependency_anchor = None;
+    }
+
+    /// Selects `edge_id` if it's currently visible through the filter; does
+    /// nothing otherwise, since there would be no row to select it onto.
+    fn select_edge(&mut self, edge_id: EdgeId) {
+        let Some(raw_index) = self.build_state.index_of(edge_id) else {
+            return;
+        };
+        if let Some(pos) = self.filtered_indices().iter().position(|&i| i == raw_index) {
+            self.list_state.select(Some(pos));
+        }
+    }
+
+    /// The entry the current `p`/`s` streak should jump from: `current` if
+    /// the selection has moved since the last jump (starting a fresh streak
+    /// at cursor `0`), or the still-active anchor if `current` is exactly
+    /// where that last jump left it.
+    fn dependency_cycle_anchor(&mut self, current: EdgeId) -> EdgeId {
+        if self.dependency_anchor.is_none() || self.dependency_last_target != Some(current) {
+            self.dependency_cursor = 0;
+            self.dependency_anchor = Some(current);
+        }
+        self.dependency_anchor.unwrap()
+    }
+
+    fn selected_entry(&self) -> Option<&BuildLogEntry> {
+        self.selected_entry_at(&self.filtered_indices())
+    }
+
+    /// Same as `selected_entry`, but against an already-computed
+    /// `filtered_indices()` result, for callers (like `ui`) that need it
+    /// alongside other uses of the filtered view and shouldn't recompute it
+    /// per-use.
+    fn selected_entry_at(&self, filtered: &[usize]) -> Option<&BuildLogEntry> {
+        self.list_state
+            .selected()
+            .and_then(|pos| filtered.get(pos))
+            .and_then(|&raw_index| self.build_state.entry_at(raw_index))
+    }
+
+    /// Jump the selection to the next predecessor of the anchor entry,
+    /// cycling back to the first one once the list is exhausted. Repeated
+    /// presses walk the whole predecessor list even though each one moves
+    /// the selection, since the anchor stays fixed for the whole streak.
+    fn jump_predecessor(&mut self) {
+        let Some(current) = self.selected_entry().map(|e| e.edge_id) else {
+            return;
+        };
+        let anchor = self.dependency_cycle_anchor(current);
+        let predecessors = self.build_state.predecessors(anchor);
+        if predecessors.is_empty() {
+            return;
+        }
+        let next = predecessors[self.dependency_cursor % predecessors.len()];
+        self.dependency_cursor += 1;
+        self.select_edge(next);
+        // Where the selection actually is: `next` may be filtered out of view.
+        self.dependency_last_target = self.selected_entry().map(|e| e.edge_id);
+    }
+
+    /// Jump the selection to the next successor of the anchor entry, cycling
+    /// back to the first one once the list is exhausted. Repeated presses
+    /// walk the whole successor list even though each one moves the
+    /// selection, since the anchor stays fixed for the whole streak.
+    fn jump_successor(&mut self) {
+        let Some(current) = self.selected_entry().map(|e| e.edge_id) else {
+            return;
+        };
+        let anchor = self.dependency_cycle_anchor(current);
+        let successors = self.build_state.successors(anchor);
+        if successors.is_empty() {
+            return;
+        }
+        let next = successors[self.dependency_cursor % successors.len()];
+        self.dependency_cursor += 1;
+        self.select_edge(next);
+        // Where the selection actually is: `next` may be filtered out of view.
+        self.dependency_last_target = self.selected_entry().map(|e| e.edge_id);
+    }
+
+    /// Step selection one edge further along the build's critical path,
+    /// wrapping back to its start once the end is reached.
+    fn jump_critical_path(&mut self) {
+        let path = self.build_state.critical_path();
+        if path.is_empty() {
+            return;
+        }
+        let edge_id = path[self.critical_path_cursor % path.len()];
+        self.critical_path_cursor += 1;
+        self.select_edge(edge_id);
     }
 
     fn read_event(&mut self) -> io::Result<UIEvent> {
@@ -188,11 +414,47 @@ impl App {
                 Ok(UIEvent::UserAction(Event::Key(key))) => {
                     if key.kind == KeyEventKind::Press {
                         use KeyCode::*;
-                        match key.code {
-                            Char('q') | Esc => return Ok(()),
-                            Char('j') | Down => self.select_log(1),
-                            Char('k') | Up => self.select_log(-1),
-                            _ => {}
+                        if self.filter_editing {
+                            match key.code {
+                                Enter | Esc => self.filter_editing = false,
+                                Backspace => {
+                                    self.filter_query.pop();
+                                    self.clamp_selection();
+                                }
+                                Char(c) => {
+                                    self.filter_query.push(c);
+                                    self.clamp_selection();
+                                }
+                                _ => {}
+                            }
+                        } else {
+                            match key.code {
+                                Char('q') | Esc => return Ok(()),
+                                Char('j') | Down => self.select_log(1),
+                                Char('k') | Up => self.select_log(-1),
+                                Char('p') => self.jump_predecessor(),
+                                Char('s') => self.jump_successor(),
+                                Char('t') => self.toggle_view_mode(),
+                                Char('c') => self.jump_critical_path(),
+                                Char('/') => self.filter_editing = true,
+                                Char('f') => {
+                                    self.failed_only = !self.failed_only;
+                                    self.clamp_selection();
+                                }
+                                Char('h') | Left if self.view_mode == ViewMode::Timeline => {
+                                    self.timeline_view.pan(-0.25)
+                                }
+                                Char('l') | Right if self.view_mode == ViewMode::Timeline => {
+                                    self.timeline_view.pan(0.25)
+                                }
+                                Char('+') | Char('=') if self.view_mode == ViewMode::Timeline => {
+                                    self.timeline_view.zoom(0.5)
+                                }
+                                Char('-') | Char('_') if self.view_mode == ViewMode::Timeline => {
+                                    self.timeline_view.zoom(2.0)
+                                }
+                                _ => {}
+                            }
                         }
                     }
                 }
@@ -210,50 +472,162 @@ impl App {
     }
 
     fn ui(&mut self, frame: &mut Frame) {
-        let [main_area, status_area] = Layout::vertical([Min(0), Length(1)]).areas(frame.size());
+        let [main_area, status_area] = Layout::vertical([Min(0), Length(2)]).areas(frame.size());
+
+        let critical_path = self.build_state.critical_path();
+        let critical_path_millis = self.build_state.critical_path_millis(critical_path);
+        let wall_clock_millis = self.build_state.wall_clock_millis();
+        let progress = self.build_state.progress();
+
+        // Computed once and reused below instead of re-filtering the whole
+        // build for each of the status line, the list, the output panel and
+        // the dependencies panel.
+        let filtered_indices = self.filtered_indices();
+        let matching = filtered_indices.len();
+        let selected_entry = self.selected_entry_at(&filtered_indices);
+
+        let eta = progress
+            .eta_millis
+            .map(format_duration_millis)
+            .unwrap_or("?".to_owned());
+
+        let filter_status = if self.filter_editing {
+            format!(" - filter: /{}_", self.filter_query)
+        } else if !self.filter_query.is_empty() || self.failed_only {
+            format!(
+                " - filter: {}{} ({matching}/{} matching, f to toggle failed-only)",
+                self.filter_query,
+                if self.failed_only { " [failed only]" } else { "" },
+                self.build_state.len(),
+            )
+        } else {
+            " - / to filter, f for failed-only".to_owned()
+        };
 
         frame.render_widget(
-            Paragraph::new(vec![Line::from(
-                format!(
-                    " {} - {} / {}",
-                    self.build_state.build_status.to_string(),
-                    self.build_state.log_entries.len(),
-                    self.build_state.total_edges
-                )
-                .dark_gray(),
-            )]),
+            Paragraph::new(vec![
+                Line::from(
+                    format!(
+                        " {} - {} / {} - critical path (c to jump): {}ms / {}ms wall clock{}",
+                        self.build_state.build_status.to_string(),
+                        self.build_state.len(),
+                        self.build_state.total_edges,
+                        critical_path_millis,
+                        wall_clock_millis,
+                        filter_status,
+                    )
+                    .dark_gray(),
+                ),
+                Line::from(
+                    format!(
+                        " {} {:.0}% - {} elapsed - {:.1} edges/s - ETA {}",
+                        progress_bar(progress.percent, 20),
+                        progress.percent,
+                        format_duration_millis(progress.elapsed_millis),
+                        progress.edges_per_sec,
+                        eta,
+                    )
+                    .dark_gray(),
+                ),
+            ]),
             status_area,
         );
 
+        if self.view_mode == ViewMode::Timeline {
+            let selected_edge_id = selected_entry.map(|e| e.edge_id);
+            self.render_timeline(frame, main_area, selected_edge_id);
+            return;
+        }
+
         let [log_area, dependency_area] =
             Layout::horizontal([Percentage(70), Percentage(30)]).areas(main_area);
 
         let [log_list_area, log_output_area] =
             Layout::vertical([Percentage(50), Percentage(50)]).areas(log_area);
 
-        let list = List::new(
-            self.build_state
-                .log_entries
-                .iter()
-                .map(log_entry_to_list_item),
-        )
-        .block(Block::default().title("Log entries").borders(Borders::ALL))
-        .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
-        .highlight_symbol(">> ")
-        .repeat_highlight_symbol(true);
+        let filtered_entries: Vec<&BuildLogEntry> = filtered_indices
+            .iter()
+            .filter_map(|&i| self.build_state.entry_at(i))
+            .collect();
+
+        let list = List::new(filtered_entries.iter().copied().map(log_entry_to_list_item))
+            .block(Block::default().title("Log entries").borders(Borders::ALL))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+            .highlight_symbol(">> ")
+            .repeat_highlight_symbol(true);
 
         frame.render_stateful_widget(list, log_list_area, &mut self.list_state);
 
-        let selected_output: String = self
-            .list_state
-            .selected()
-            .and_then(|i| self.build_state.log_entries.get(i))
-            .and_then(|e| e.output.clone())
-            .unwrap_or(String::new());
-        let output_par =
-            Paragraph::new(selected_output).block(Block::bordered().title("Log Output"));
-        frame.render_widget(output_par, log_output_area);
-
-        frame.render_widget(Block::bordered().title("Dependencies"), dependency_area);
+        let output_block = Block::bordered().title("Log Output");
+        let inner = output_block.inner(log_output_area);
+        let lines = selected_entry
+            .and_then(|e| e.output.as_deref())
+            .map(|output| vt::render_output(output, inner.width, inner.height))
+            .unwrap_or_default();
+        frame.render_widget(output_block, log_output_area);
+        frame.render_widget(Paragraph::new(lines), inner);
+
+        self.render_dependencies(frame, dependency_area, selected_entry);
+
+        // Rendered last: it needs `&mut self.list_state`, which can't be
+        // borrowed while `selected_entry` still borrows `self`.
+        frame.render_stateful_widget(list, log_list_area, &mut self.list_state);
+    }
+
+    fn render_dependencies(&self, frame: &mut Frame, area: Rect, entry: Option<&BuildLogEntry>) {
+        let Some(entry) = entry else {
+            frame.render_widget(Block::bordered().title("Dependencies"), area);
+            return;
+        };
+
+        let mut lines: Vec<Line> = Vec::new();
+        lines.push(Line::from("Inputs ([E]xplicit/[I]mplicit/[O]rder-only):".bold()));
+        for input in &entry.inputs {
+            lines.push(input_line(input));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Predecessors (p to jump):".bold()));
+        for edge_id in self.build_state.predecessors(entry.edge_id) {
+            if let Some(e) = self.build_state.get(edge_id) {
+                lines.push(edge_summary_line(e));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("Successors (s to jump):".bold()));
+        for edge_id in self.build_state.successors(entry.edge_id) {
+            if let Some(e) = self.build_state.get(edge_id) {
+                lines.push(edge_summary_line(e));
+            }
+        }
+
+        let paragraph =
+            Paragraph::new(lines).block(Block::bordered().title("Dependencies"));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn render_timeline(&mut self, frame: &mut Frame, area: Rect, selected_edge_id: Option<EdgeId>) {
+        let entries: Vec<&BuildLogEntry> = self.build_state.entries_in_order().collect();
+        let inner = Block::bordered().inner(area);
+        let hidden_lanes = lanes_needed(&entries).saturating_sub(inner.height as usize);
+        let overflow_hint = if hidden_lanes > 0 {
+            format!(" - +{hidden_lanes} more lanes hidden")
+        } else {
+            String::new()
+        };
+
+        let block = Block::bordered().title(format!(
+            "Timeline - {}ms window, h/l pan, +/- zoom, t to go back{}",
+            self.timeline_view.visible_millis, overflow_hint
+        ));
+        frame.render_widget(block, area);
+
+        let timeline = Timeline {
+            entries,
+            selected_edge_id,
+            view: &mut self.timeline_view,
+        };
+        frame.render_widget(timeline, inner);
     }
 }
@@ -0,0 +1,186 @@
+//! A Gantt-style view of edge execution over wall-clock time, so
+//! serialization stalls and the overall shape of the build's parallelism
+//! are visible at a glance.
+
+use ratatui::prelude::*;
+
+use crate::build_log::{BuildLogEntry, EdgeId};
+
+/// Pan/zoom state for the timeline view. `visible_millis` of `0` means "not
+/// yet sized"; the first render fits the whole build into view.
+pub struct TimelineView {
+    pub offset_millis: i64,
+    pub visible_millis: i64,
+}
+
+impl TimelineView {
+    pub fn new() -> Self {
+        Self {
+            offset_millis: 0,
+            visible_millis: 0,
+        }
+    }
+
+    pub fn pan(&mut self, fraction: f64) {
+        self.offset_millis += (self.visible_millis as f64 * fraction) as i64;
+        self.offset_millis = self.offset_millis.max(0);
+    }
+
+    pub fn zoom(&mut self, factor: f64) {
+        let new_visible = (self.visible_millis as f64 * factor) as i64;
+        self.visible_millis = new_visible.max(50);
+    }
+
+    fn ensure_sized(&mut self, total_span_millis: i64) {
+        if self.visible_millis == 0 {
+            self.visible_millis = total_span_millis.max(1000);
+        }
+    }
+}
+
+/// Greedily packs entries into the fewest rows such that no two entries in
+/// the same row overlap in time, keeping concurrently running edges visible
+/// on separate lines.
+fn assign_lanes(entries: &[&BuildLogEntry], now_millis: i64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..entries.len()).collect();
+    order.sort_by_key(|&i| entries[i].start_time_millis);
+
+    let mut lane_end_millis: Vec<i64> = Vec::new();
+    let mut lanes = vec![0usize; entries.len()];
+    for i in order {
+        let entry = entries[i];
+        let end = entry.end_time_millis.unwrap_or(now_millis);
+        match lane_end_millis.iter().position(|&e| e <= entry.start_time_millis) {
+            Some(lane) => {
+                lane_end_millis[lane] = end;
+                lanes[i] = lane;
+            }
+            None => {
+                lane_end_millis.push(end);
+                lanes[i] = lane_end_millis.len() - 1;
+            }
+        }
+    }
+    lanes
+}
+
+/// How many lanes [`assign_lanes`] would need to keep every entry in
+/// `entries` from overlapping, so callers can warn when a build is more
+/// parallel than there are rows to show it in, instead of silently dropping
+/// the overflow lanes off the bottom.
+pub fn lanes_needed(entries: &[&BuildLogEntry]) -> usize {
+    let now_millis = entries
+        .iter()
+        .flat_map(|e| [Some(e.start_time_millis), e.end_time_millis])
+        .flatten()
+        .max()
+        .unwrap_or(0);
+    assign_lanes(entries, now_millis)
+        .into_iter()
+        .max()
+        .map_or(0, |lane| lane + 1)
+}
+
+pub struct Timeline<'a> {
+    pub entries: Vec<&'a BuildLogEntry>,
+    pub selected_edge_id: Option<EdgeId>,
+    pub view: &'a mut TimelineView,
+}
+
+impl<'a> Widget for Timeline<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 || self.entries.is_empty() {
+            return;
+        }
+
+        let build_start_millis = self
+            .entries
+            .iter()
+            .map(|e| e.start_time_millis)
+            .min()
+            .unwrap_or(0);
+        let now_millis = self
+            .entries
+            .iter()
+            .flat_map(|e| [Some(e.start_time_millis), e.end_time_millis])
+            .flatten()
+            .max()
+            .unwrap_or(build_start_millis);
+
+        self.view.ensure_sized(now_millis - build_start_millis);
+
+        let lanes = assign_lanes(&self.entries, now_millis);
+        let millis_per_col = self.view.visible_millis as f64 / area.width as f64;
+
+        for (entry, lane) in self.entries.iter().zip(lanes) {
+            if lane as u16 >= area.height {
+                continue;
+            }
+            let rel_start =
+                entry.start_time_millis - build_start_millis - self.view.offset_millis;
+            let rel_end = entry.end_time_millis.unwrap_or(now_millis) - build_start_millis
+                - self.view.offset_millis;
+
+            let start_col = (rel_start as f64 / millis_per_col).floor();
+            let end_col = ((rel_end as f64 / millis_per_col).ceil()).max(start_col + 1.0);
+            if end_col <= 0.0 || start_col >= area.width as f64 {
+                continue;
+            }
+            let start_col = start_col.max(0.0) as u16;
+            let end_col = (end_col as u16).min(area.width);
+            if end_col <= start_col {
+                continue;
+            }
+
+            let color = if entry.success == Some(false) {
+                Color::Red
+            } else if entry.end_time_millis.is_none() {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            let mut style = Style::default().bg(color);
+            if Some(entry.edge_id) == self.selected_edge_id {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            let bar = " ".repeat((end_col - start_col) as usize);
+            buf.set_string(area.x + start_col, area.y + lane as u16, bar, style);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(edge_id: usize, start_time_millis: i64, end_time_millis: i64) -> BuildLogEntry {
+        BuildLogEntry {
+            edge_id: EdgeId(edge_id),
+            success: Some(true),
+            command: String::new(),
+            compiler: String::new(),
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            output: None,
+            output_lower: None,
+            start_time_millis,
+            end_time_millis: Some(end_time_millis),
+        }
+    }
+
+    #[test]
+    fn lanes_needed_packs_non_overlapping_entries_into_the_same_lane() {
+        let a = entry(0, 0, 100);
+        let b = entry(1, 50, 150); // overlaps `a`, needs its own lane
+        let c = entry(2, 120, 200); // starts after `a` ends, reuses its lane
+        let entries = vec![&a, &b, &c];
+
+        assert_eq!(lanes_needed(&entries), 2);
+    }
+
+    #[test]
+    fn lanes_needed_is_zero_for_no_entries() {
+        assert_eq!(lanes_needed(&[]), 0);
+    }
+}
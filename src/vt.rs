@@ -0,0 +1,65 @@
+//! Renders captured command output through an in-memory terminal emulator,
+//! so ANSI color codes and carriage-return progress bars come out the way a
+//! real terminal would show them instead of as literal escape bytes.
+
+use ratatui::prelude::*;
+
+/// Feed `output` through a `rows`x`cols` virtual terminal and turn the
+/// resulting screen into styled lines ready to hand to a ratatui widget.
+pub fn render_output(output: &str, cols: u16, rows: u16) -> Vec<Line<'static>> {
+    if cols == 0 || rows == 0 {
+        return Vec::new();
+    }
+
+    let mut parser = vt100::Parser::new(rows, cols, 0);
+    // A real terminal's line discipline turns `\n` into `\r\n`; the emulator
+    // doesn't, so do it here or every line would start where the last ended.
+    parser.process(output.replace('\n', "\r\n").as_bytes());
+    let screen = parser.screen();
+
+    (0..rows)
+        .map(|row| {
+            let spans: Vec<Span<'static>> = (0..cols)
+                .map(|col| screen.cell(row, col))
+                .map(|cell| match cell {
+                    Some(cell) if !cell.contents().is_empty() => {
+                        Span::styled(cell.contents(), cell_style(cell))
+                    }
+                    _ => Span::raw(" "),
+                })
+                .collect();
+            Line::from(spans)
+        })
+        .collect()
+}
+
+fn cell_style(cell: &vt100::Cell) -> Style {
+    let mut style = Style::default();
+    if let Some(fg) = vt_color_to_ratatui(cell.fgcolor()) {
+        style = style.fg(fg);
+    }
+    if let Some(bg) = vt_color_to_ratatui(cell.bgcolor()) {
+        style = style.bg(bg);
+    }
+    if cell.bold() {
+        style = style.add_modifier(Modifier::BOLD);
+    }
+    if cell.italic() {
+        style = style.add_modifier(Modifier::ITALIC);
+    }
+    if cell.underline() {
+        style = style.add_modifier(Modifier::UNDERLINED);
+    }
+    if cell.inverse() {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    style
+}
+
+fn vt_color_to_ratatui(color: vt100::Color) -> Option<Color> {
+    match color {
+        vt100::Color::Default => None,
+        vt100::Color::Idx(i) => Some(Color::Indexed(i)),
+        vt100::Color::Rgb(r, g, b) => Some(Color::Rgb(r, g, b)),
+    }
+}